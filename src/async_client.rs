@@ -0,0 +1,421 @@
+//! An async mirror of [`AocClient`](crate::AocClient), for use inside an async runtime without
+//! spawning blocking tasks for every request.
+//!
+//! This requires the `async` feature. The public surface matches the blocking client as closely
+//! as possible - see [`AocClient`](crate::AocClient) for documentation of the individual methods.
+//!
+//! Call [`AsyncAocClient::close`] before dropping a client where possible: it persists the cache
+//! via `spawn_blocking` instead of relying on `Drop`, which saves synchronously on whatever thread
+//! the client happens to be dropped on.
+
+use crate::cache::{FileCacheProvider, PersistentCacheProvider};
+use crate::client_core::{ClientCore, SubmitDecision};
+use crate::example_parse::Example;
+use crate::puzzle::Puzzle;
+use crate::shared;
+use crate::submission::SubmissionResult;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static MIN_TIME_BETWEEN_REQUESTS: Duration = Duration::from_secs(180);
+
+pub struct AsyncAocClient<C: PersistentCacheProvider + Send + 'static> {
+    session: String,
+    client: reqwest::Client,
+    core: ClientCore,
+    persistent_cache: Arc<Mutex<C>>,
+    closed: bool,
+}
+
+impl AsyncAocClient<FileCacheProvider> {
+    /// Create an `AsyncAocClient` using the session token stored in the environment variable
+    /// `AOC_SESSION`.
+    pub async fn new_from_env() -> Self {
+        Self::new(
+            std::env::var("AOC_SESSION")
+                .expect("AOC_SESSION environment variable not found!")
+                .to_string(),
+        )
+        .await
+    }
+
+    /// Create an `AsyncAocClient` using the given session token and the default cache directory.
+    pub async fn new(session: String) -> Self {
+        Self::new_with_custom_cache(session, FileCacheProvider::new()).await
+    }
+}
+
+impl<C: PersistentCacheProvider + Send + 'static> AsyncAocClient<C> {
+    fn make_client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .user_agent(shared::user_agent())
+            .build()
+            .unwrap()
+    }
+
+    async fn throttle(&mut self) -> bool {
+        match shared::throttle_remaining(self.core.throttle_timestamp, MIN_TIME_BETWEEN_REQUESTS) {
+            Ok(Some(sleep_duration)) => {
+                eprintln!(
+                    "libaoc: request throttled - sleeping for {}s",
+                    sleep_duration.as_secs_f64()
+                );
+                tokio::time::sleep(sleep_duration).await;
+                self.core.throttle_timestamp = SystemTime::now();
+                true
+            }
+            Ok(None) => {
+                self.core.throttle_timestamp = SystemTime::now();
+                true
+            }
+            Err(()) => {
+                eprintln!("libaoc: warning: received SystemTimeError while processing throttle, sleeping for 1 second and retrying...");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                false
+            }
+        }
+    }
+
+    /// Create an `AsyncAocClient` using the given session token and cache provider.
+    pub async fn new_with_custom_cache(session: String, cache_provider: C) -> Self {
+        let persistent_cache = Arc::new(Mutex::new(cache_provider));
+        let cache = persistent_cache.clone();
+        let throttle_timestamp = tokio::task::spawn_blocking(move || {
+            cache.lock().unwrap().load_throttle_timestamp()
+        })
+        .await
+        .unwrap()
+        .unwrap_or(UNIX_EPOCH);
+
+        AsyncAocClient {
+            session,
+            persistent_cache,
+            client: Self::make_client(),
+            core: ClientCore::new(throttle_timestamp),
+            closed: false,
+        }
+    }
+
+    /// Set a freshness policy for cached examples: once an example with no part 2 data is older
+    /// than `ttl`, `get_example` will re-fetch it instead of serving the stale cache entry.
+    ///
+    /// This exists because the part 2 example (and answer) only appear in the HTML once the
+    /// account has completed part 1, so a cache populated before that point would otherwise serve
+    /// a part-1-only example forever.
+    pub fn set_example_ttl(&mut self, ttl: Duration) {
+        self.core.set_example_ttl(ttl);
+    }
+
+    /// Get the input text for the Advent of Code puzzle for the given day and year, bypassing the
+    /// cache. Only use this if you believe the cached input is corrupted.
+    pub async fn get_input_without_cache(
+        &mut self,
+        year: i32,
+        day: i32,
+    ) -> reqwest::Result<String> {
+        if !self.throttle().await {
+            return Box::pin(self.get_input_without_cache(year, day)).await;
+        }
+
+        let text = match self
+            .client
+            .get(shared::input_url(year, day))
+            .header("Cookie", shared::cookie_header(&self.session))
+            .send()
+            .await
+        {
+            Ok(r) => r.text().await,
+            Err(e) => Err(e),
+        };
+
+        if let Ok(text) = &text {
+            self.core.mem_cache.insert((year, day), text.clone());
+        }
+
+        text
+    }
+
+    /// Get the input text for the Advent of Code puzzle for the given day and year, bypassing the
+    /// file cache but using any value in the in-memory cache.
+    pub async fn get_input_without_persistent_cache(
+        &mut self,
+        year: i32,
+        day: i32,
+    ) -> reqwest::Result<String> {
+        if let Some(text) = self.core.mem_cache.get(&(year, day)) {
+            return Ok(text.clone());
+        }
+        self.get_input_without_cache(year, day).await
+    }
+
+    /// Get the input text for the Advent of Code puzzle for the given day and year.
+    pub async fn get_input(&mut self, year: i32, day: i32) -> reqwest::Result<String> {
+        if let Some(text) = self.core.mem_cache.get(&(year, day)) {
+            return Ok(text.clone());
+        }
+
+        let cache = self.persistent_cache.clone();
+        let loaded = tokio::task::spawn_blocking(move || cache.lock().unwrap().load((year, day)))
+            .await
+            .unwrap();
+
+        if let Some(text) = loaded {
+            self.core.mem_cache.insert((year, day), text.clone());
+            return Ok(text);
+        }
+
+        self.get_input_without_cache(year, day).await
+    }
+
+    /// Fetch the day page HTML directly from Advent of Code, bypassing the cache, and record it
+    /// in the day page cache. `get_example` and `get_puzzle` both parse this same page, so sharing
+    /// one fetch/cache of it means asking for both doesn't cost two requests (and two trips
+    /// through the throttle) for identical content.
+    async fn fetch_day_page(&mut self, year: i32, day: i32) -> reqwest::Result<String> {
+        if !self.throttle().await {
+            return Box::pin(self.fetch_day_page(year, day)).await;
+        }
+
+        let html = match self
+            .client
+            .get(shared::day_url(year, day))
+            .header("Cookie", shared::cookie_header(&self.session))
+            .send()
+            .await
+        {
+            Ok(r) => r.text().await,
+            Err(e) => Err(e),
+        }?;
+
+        self.core.day_page_cache.insert((year, day), html.clone());
+        self.core
+            .day_page_fetch_times
+            .insert((year, day), SystemTime::now());
+
+        Ok(html)
+    }
+
+    /// Get the example input and (possibly unreliable) answer(s) for the given day and year,
+    /// bypassing the cache.
+    pub async fn get_example_without_cache(
+        &mut self,
+        year: i32,
+        day: i32,
+        _part: i32,
+    ) -> reqwest::Result<Option<Example>> {
+        self.fetch_day_page(year, day).await.map(Example::parse_example)
+    }
+
+    /// Get the example input and (possibly unreliable) answer(s) for the given day and year,
+    /// bypassing the persistent cache but using the in-memory cache.
+    pub async fn get_example_without_persistent_cache(
+        &mut self,
+        year: i32,
+        day: i32,
+        part: i32,
+    ) -> reqwest::Result<Option<Example>> {
+        if let Some(html) = self.core.day_page_cache.get(&(year, day)) {
+            return Ok(Example::parse_example(html.clone()));
+        }
+        self.get_example_without_cache(year, day, part).await
+    }
+
+    /// Whether the cached day page for `key` is stale under the configured `example_ttl`: the
+    /// example it holds has no part 2 data yet, and it was fetched longer ago than the TTL allows.
+    async fn example_is_stale(&mut self, key: (i32, i32), example: &Option<Example>) -> bool {
+        if !self.core.day_page_fetch_times.contains_key(&key) {
+            let cache = self.persistent_cache.clone();
+            let fetched_at = tokio::task::spawn_blocking(move || {
+                cache.lock().unwrap().load_day_page_fetch_time(key)
+            })
+            .await
+            .unwrap();
+            if let Some(fetched_at) = fetched_at {
+                self.core.day_page_fetch_times.insert(key, fetched_at);
+            }
+        }
+
+        self.core
+            .example_is_stale(example, self.core.day_page_fetch_times.get(&key).copied())
+    }
+
+    /// Get the example input and (possibly unreliable) answer(s) for the given day and year.
+    ///
+    /// If an example TTL has been set with `set_example_ttl`, a cached example with no part 2 data
+    /// older than the TTL is treated as stale and re-fetched instead of returned.
+    pub async fn get_example(
+        &mut self,
+        year: i32,
+        day: i32,
+        part: i32,
+    ) -> reqwest::Result<Option<Example>> {
+        let key = (year, day);
+        let cached_html = match self.core.day_page_cache.get(&key).cloned() {
+            Some(html) => Some(html),
+            None => {
+                let cache = self.persistent_cache.clone();
+                tokio::task::spawn_blocking(move || cache.lock().unwrap().load_day_page(key))
+                    .await
+                    .unwrap()
+            }
+        };
+
+        if let Some(html) = cached_html {
+            self.core.day_page_cache.insert(key, html.clone());
+            let example = Example::parse_example(html);
+            if !self.example_is_stale(key, &example).await {
+                return Ok(example);
+            }
+        }
+
+        self.get_example_without_cache(year, day, part).await
+    }
+
+    /// Get the puzzle description (titles and prose, converted to Markdown) for the given day and
+    /// year, bypassing the cache.
+    pub async fn get_puzzle_without_cache(
+        &mut self,
+        year: i32,
+        day: i32,
+    ) -> reqwest::Result<Option<Puzzle>> {
+        self.fetch_day_page(year, day).await.map(Puzzle::parse)
+    }
+
+    /// Get the puzzle description (titles and prose, converted to Markdown) for the given day and
+    /// year.
+    pub async fn get_puzzle(&mut self, year: i32, day: i32) -> reqwest::Result<Option<Puzzle>> {
+        let key = (year, day);
+        if let Some(html) = self.core.day_page_cache.get(&key) {
+            return Ok(Puzzle::parse(html.clone()));
+        }
+
+        let cache = self.persistent_cache.clone();
+        let loaded = tokio::task::spawn_blocking(move || cache.lock().unwrap().load_day_page(key))
+            .await
+            .unwrap();
+
+        if let Some(html) = loaded {
+            self.core.day_page_cache.insert(key, html.clone());
+            return Ok(Puzzle::parse(html));
+        }
+
+        self.get_puzzle_without_cache(year, day).await
+    }
+
+    async fn load_submission_state(&mut self, key: (i32, i32, i32)) {
+        if !self.core.submission_cache.contains_key(&key) {
+            let cache = self.persistent_cache.clone();
+            let submissions =
+                tokio::task::spawn_blocking(move || cache.lock().unwrap().load_submissions(key))
+                    .await
+                    .unwrap();
+            self.core.submission_cache.insert(key, submissions);
+        }
+        if !self.core.answer_cooldowns.contains_key(&key) {
+            let cache = self.persistent_cache.clone();
+            let cooldown = tokio::task::spawn_blocking(move || {
+                cache.lock().unwrap().load_answer_cooldown(key)
+            })
+            .await
+            .unwrap();
+            if let Some(until) = cooldown {
+                self.core.answer_cooldowns.insert(key, until);
+            }
+        }
+    }
+
+    /// Submit an answer for the given year, day and part.
+    pub async fn submit(
+        &mut self,
+        year: i32,
+        day: i32,
+        part: i32,
+        answer: &str,
+    ) -> reqwest::Result<SubmissionResult> {
+        let key = (year, day, part);
+        self.load_submission_state(key).await;
+
+        if let SubmitDecision::Known(result) = self.core.submit_decision(key, answer) {
+            return Ok(result);
+        }
+
+        if let Some(remaining) = self.core.cooldown_remaining(key) {
+            eprintln!(
+                "libaoc: submission throttled - sleeping for {}s",
+                remaining.as_secs_f64()
+            );
+            tokio::time::sleep(remaining).await;
+        }
+
+        if !self.throttle().await {
+            return Box::pin(self.submit(year, day, part, answer)).await;
+        }
+
+        let text = match self
+            .client
+            .post(shared::submit_url(year, day))
+            .header("Cookie", shared::cookie_header(&self.session))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(shared::submit_body(part, answer))
+            .send()
+            .await
+        {
+            Ok(r) => r.text().await,
+            Err(e) => Err(e),
+        };
+
+        let result = text.map(SubmissionResult::parse)?;
+        self.core.record_submission_result(key, answer, &result);
+
+        Ok(result)
+    }
+
+    /// Persist everything cached so far and consume the client.
+    ///
+    /// `Drop` also persists the cache as a fallback, but it does so with a blocking call on
+    /// whatever thread the client happens to be dropped on, which is unsafe to do from inside an
+    /// async runtime. Prefer calling `close` before letting the client go out of scope so the save
+    /// runs via `spawn_blocking` instead.
+    pub async fn close(mut self) {
+        let cache = self.persistent_cache.clone();
+        let mem_cache = self.core.mem_cache.clone();
+        let day_page_cache = self.core.day_page_cache.clone();
+        let day_page_fetch_times = self.core.day_page_fetch_times.clone();
+        let submission_cache = self.core.submission_cache.clone();
+        let answer_cooldowns = self.core.answer_cooldowns.clone();
+        let throttle_timestamp = self.core.throttle_timestamp;
+
+        let _ = tokio::task::spawn_blocking(move || {
+            cache.lock().unwrap().save_all(
+                &mem_cache,
+                &day_page_cache,
+                &day_page_fetch_times,
+                &submission_cache,
+                &answer_cooldowns,
+                throttle_timestamp,
+            );
+        })
+        .await;
+
+        self.closed = true;
+    }
+}
+
+impl<C: PersistentCacheProvider + Send + 'static> Drop for AsyncAocClient<C> {
+    /// Fallback persistence for clients that weren't explicitly `close`d: this runs the cache save
+    /// synchronously on whatever thread drops the client, which will block an async executor
+    /// thread if that's where the drop happens. Call `close` instead when you can.
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+        self.persistent_cache.lock().unwrap().save_all(
+            &self.core.mem_cache,
+            &self.core.day_page_cache,
+            &self.core.day_page_fetch_times,
+            &self.core.submission_cache,
+            &self.core.answer_cooldowns,
+            self.core.throttle_timestamp,
+        );
+    }
+}
@@ -1,34 +1,140 @@
+use crate::submission::SubmissionOutcome;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{env, fs};
 
+/// Write `contents` to `file` without ever leaving a truncated file in its place: the data is
+/// written to a temporary file in the same directory first, then renamed over `file`, which is
+/// atomic on the filesystems we care about.
+fn atomic_write(file: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp_file = file.with_extension(format!("tmp.{}", std::process::id()));
+    fs::write(&tmp_file, contents)?;
+    fs::rename(&tmp_file, file)
+}
+
+/// A cheap, non-cryptographic hash used only to detect truncated/corrupted cache files, not for
+/// any security purpose.
+fn content_hash(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Metadata sidecar stored alongside each cached input so corruption can be detected on load.
+struct CacheMeta {
+    content_length: usize,
+    hash: u64,
+}
+
+impl CacheMeta {
+    fn for_content(contents: &str) -> Self {
+        Self {
+            content_length: contents.len(),
+            hash: content_hash(contents),
+        }
+    }
+
+    fn matches(&self, contents: &str) -> bool {
+        self.content_length == contents.len() && self.hash == content_hash(contents)
+    }
+
+    fn to_json(&self, fetched_at: SystemTime) -> String {
+        format!(
+            "{{\"fetched_at\":{},\"content_length\":{},\"hash\":{}}}",
+            fetched_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64(),
+            self.content_length,
+            self.hash,
+        )
+    }
+
+    fn from_json(s: &str) -> Option<Self> {
+        Some(Self {
+            content_length: Self::extract_field(s, "content_length")?,
+            hash: Self::extract_field(s, "hash")?,
+        })
+    }
+
+    /// Extract and parse a numeric field from the sidecar JSON. Parses directly as `T` rather
+    /// than going through `f64`, since `f64`'s 52-bit mantissa can't round-trip a full `u64` hash.
+    fn extract_field<T: FromStr>(s: &str, field: &str) -> Option<T> {
+        let marker = format!("\"{field}\":");
+        let start = s.find(&marker)? + marker.len();
+        let rest = &s[start..];
+        let end = rest.find([',', '}']).unwrap_or(rest.len());
+        rest[..end].trim().parse().ok()
+    }
+}
+
 pub trait PersistentCacheProvider {
     fn save(&mut self, key: (i32, i32), text: String);
 
-    fn save_example(&mut self, key: (i32, i32, i32), html: String);
+    /// Persist the day page HTML backing both `AocClient::get_example` and
+    /// `AocClient::get_puzzle` - both parse the same page, so it's cached once per `(year, day)`
+    /// rather than once per caller.
+    fn save_day_page(&mut self, key: (i32, i32), html: String);
+
+    /// Record when the day page for the given `(year, day)` was last fetched, so `AocClient`'s
+    /// example TTL can tell a stale cache entry from a fresh one.
+    fn save_day_page_fetch_time(&mut self, key: (i32, i32), timestamp: SystemTime);
 
     fn save_throttle_timestamp(&mut self, timestamp: SystemTime);
 
+    /// Record the outcome of submitting `answer` for the given `(year, day, part)`.
+    fn save_submission(&mut self, key: (i32, i32, i32), answer: String, outcome: SubmissionOutcome);
+
+    /// Record that submissions for the given `(year, day, part)` must wait until `until`.
+    fn save_answer_cooldown(&mut self, key: (i32, i32, i32), until: SystemTime);
+
     fn load(&self, key: (i32, i32)) -> Option<String>;
 
-    fn load_example(&self, key: (i32, i32, i32)) -> Option<String>;
+    /// Load the day page HTML backing both `AocClient::get_example` and `AocClient::get_puzzle`.
+    fn load_day_page(&self, key: (i32, i32)) -> Option<String>;
+
+    /// Load when the day page for the given `(year, day)` was last fetched, if known.
+    fn load_day_page_fetch_time(&self, key: (i32, i32)) -> Option<SystemTime>;
 
     fn load_throttle_timestamp(&self) -> Option<SystemTime>;
 
+    /// Load every answer already tried for the given `(year, day, part)`, along with its outcome.
+    fn load_submissions(&self, key: (i32, i32, i32)) -> HashMap<String, SubmissionOutcome>;
+
+    /// Load the time at which submissions for the given `(year, day, part)` may resume, if any.
+    fn load_answer_cooldown(&self, key: (i32, i32, i32)) -> Option<SystemTime>;
+
     fn save_all(
         &mut self,
         real: &HashMap<(i32, i32), String>,
-        examples: &HashMap<(i32, i32, i32), String>,
+        day_pages: &HashMap<(i32, i32), String>,
+        day_page_fetch_times: &HashMap<(i32, i32), SystemTime>,
+        submissions: &HashMap<(i32, i32, i32), HashMap<String, SubmissionOutcome>>,
+        answer_cooldowns: &HashMap<(i32, i32, i32), SystemTime>,
         throttle_timestamp: SystemTime,
     ) {
         self.save_throttle_timestamp(throttle_timestamp);
         for (key, text) in real {
             self.save(*key, text.clone());
         }
-        for (key, val) in examples {
-            self.save_example(*key, val.clone());
+        for (key, html) in day_pages {
+            self.save_day_page(*key, html.clone());
+        }
+        for (key, timestamp) in day_page_fetch_times {
+            self.save_day_page_fetch_time(*key, *timestamp);
+        }
+        for (key, outcomes) in submissions {
+            for (answer, outcome) in outcomes {
+                self.save_submission(*key, answer.clone(), outcome.clone());
+            }
+        }
+        for (key, until) in answer_cooldowns {
+            self.save_answer_cooldown(*key, *until);
         }
     }
 }
@@ -64,25 +170,51 @@ impl PersistentCacheProvider for FileCacheProvider {
             return;
         }
         let file = self.cache_dir.join(format!("libaoc/{year}/{day}.txt"));
-        if let Err(e) = fs::write(file, text) {
+        let meta_file = self.cache_dir.join(format!("libaoc/{year}/{day}.meta.json"));
+
+        let meta = CacheMeta::for_content(&text);
+        if let Err(e) = atomic_write(&file, text.as_bytes()) {
             eprintln!("libaoc: warning: failed to save cache file: {e}");
+            return;
+        }
+        if let Err(e) = atomic_write(&meta_file, meta.to_json(SystemTime::now()).as_bytes()) {
+            eprintln!("libaoc: warning: failed to save cache metadata: {e}");
         }
     }
 
-    fn save_example(&mut self, key: (i32, i32, i32), html: String) {
-        let (year, day, part) = key;
-        let dir = self.cache_dir.join(format!("libaoc/examples/{year}"));
-        if let Err(e) = fs::create_dir_all(dir) {
+    fn save_day_page(&mut self, key: (i32, i32), html: String) {
+        let (year, day) = key;
+        let dir = self.cache_dir.join(format!("libaoc/pages/{year}"));
+        if let Err(e) = fs::create_dir_all(&dir) {
             eprintln!("libaoc: warning: failed to create directory for caching: {e}");
+            return;
         }
-        let file = self
-            .cache_dir
-            .join(format!("libaoc/examples/{year}/{day}_{part}.html"));
+        let file = dir.join(format!("{day}.html"));
         if let Err(e) = fs::write(file, html) {
             eprintln!("libaoc: warning: failed to save cache file: {e}");
         }
     }
 
+    fn save_day_page_fetch_time(&mut self, key: (i32, i32), timestamp: SystemTime) {
+        let (year, day) = key;
+        let dir = self.cache_dir.join(format!("libaoc/pages/{year}"));
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("libaoc: warning: failed to create directory for caching: {e}");
+            return;
+        }
+        let file = dir.join(format!("{day}.fetched_at"));
+        if let Err(e) = fs::write(
+            file,
+            timestamp
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64()
+                .to_string(),
+        ) {
+            eprintln!("libaoc: warning: failed to save day page fetch time: {e}");
+        }
+    }
+
     fn save_throttle_timestamp(&mut self, timestamp: SystemTime) {
         let dir = self.cache_dir.join("libaoc");
         if let Err(e) = fs::create_dir_all(dir) {
@@ -108,6 +240,26 @@ impl PersistentCacheProvider for FileCacheProvider {
     fn load(&self, key: (i32, i32)) -> Option<String> {
         let (year, day) = key;
         let file = self.cache_dir.join(format!("libaoc/{year}/{day}.txt"));
+        if !file.exists() {
+            return None;
+        }
+        let text = fs::read_to_string(file).ok()?;
+
+        let meta_file = self.cache_dir.join(format!("libaoc/{year}/{day}.meta.json"));
+        if let Ok(meta_json) = fs::read_to_string(meta_file) {
+            let meta = CacheMeta::from_json(&meta_json)?;
+            if !meta.matches(&text) {
+                eprintln!("libaoc: warning: cached input for {year} day {day} failed its integrity check, forcing a refetch");
+                return None;
+            }
+        }
+
+        Some(text)
+    }
+
+    fn load_day_page(&self, key: (i32, i32)) -> Option<String> {
+        let (year, day) = key;
+        let file = self.cache_dir.join(format!("libaoc/pages/{year}/{day}.html"));
         if file.exists() {
             fs::read_to_string(file).ok()
         } else {
@@ -115,13 +267,13 @@ impl PersistentCacheProvider for FileCacheProvider {
         }
     }
 
-    fn load_example(&self, key: (i32, i32, i32)) -> Option<String> {
-        let (year, day, part) = key;
+    fn load_day_page_fetch_time(&self, key: (i32, i32)) -> Option<SystemTime> {
+        let (year, day) = key;
         let file = self
             .cache_dir
-            .join(format!("libaoc/examples/{year}/{day}_{part}.html"));
+            .join(format!("libaoc/pages/{year}/{day}.fetched_at"));
         if file.exists() {
-            fs::read_to_string(file).ok()
+            Some(UNIX_EPOCH + Duration::from_secs_f64(f64::from_str(&fs::read_to_string(file).ok()?).ok()?))
         } else {
             None
         }
@@ -138,4 +290,113 @@ impl PersistentCacheProvider for FileCacheProvider {
             None
         }
     }
+
+    fn save_submission(
+        &mut self,
+        key: (i32, i32, i32),
+        answer: String,
+        outcome: SubmissionOutcome,
+    ) {
+        let (year, day, part) = key;
+        let dir = self.cache_dir.join(format!("libaoc/submissions/{year}"));
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("libaoc: warning: failed to create directory for caching: {e}");
+            return;
+        }
+        let file = dir.join(format!("{day}_{part}.txt"));
+
+        let mut submissions = self.load_submissions(key);
+        submissions.insert(answer, outcome);
+        let contents = submissions
+            .iter()
+            .map(|(answer, outcome)| format!("{answer}\t{}", outcome.to_cache_string()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Err(e) = fs::write(file, contents) {
+            eprintln!("libaoc: warning: failed to save cache file: {e}");
+        }
+    }
+
+    fn save_answer_cooldown(&mut self, key: (i32, i32, i32), until: SystemTime) {
+        let (year, day, part) = key;
+        let dir = self.cache_dir.join(format!("libaoc/submissions/{year}"));
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("libaoc: warning: failed to create directory for caching: {e}");
+            return;
+        }
+        let file = dir.join(format!("{day}_{part}.cooldown"));
+        if let Err(e) = fs::write(
+            file,
+            until
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64()
+                .to_string(),
+        ) {
+            eprintln!("libaoc: warning: failed to save answer cooldown: {e}");
+        }
+    }
+
+    fn load_submissions(&self, key: (i32, i32, i32)) -> HashMap<String, SubmissionOutcome> {
+        let (year, day, part) = key;
+        let file = self
+            .cache_dir
+            .join(format!("libaoc/submissions/{year}/{day}_{part}.txt"));
+
+        let mut submissions = HashMap::new();
+        if let Ok(contents) = fs::read_to_string(file) {
+            for line in contents.lines() {
+                if let Some((answer, outcome)) = line.split_once('\t') {
+                    if let Some(outcome) = SubmissionOutcome::from_cache_string(outcome) {
+                        submissions.insert(answer.to_string(), outcome);
+                    }
+                }
+            }
+        }
+        submissions
+    }
+
+    fn load_answer_cooldown(&self, key: (i32, i32, i32)) -> Option<SystemTime> {
+        let (year, day, part) = key;
+        let file = self
+            .cache_dir
+            .join(format!("libaoc/submissions/{year}/{day}_{part}.cooldown"));
+        if file.exists() {
+            Some(UNIX_EPOCH + Duration::from_secs_f64(f64::from_str(&fs::read_to_string(file).ok()?).ok()?))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn save_then_load_input_roundtrips() {
+        let dir = env::temp_dir().join(format!("libaoc-cache-test-{}", std::process::id()));
+        let mut provider = FileCacheProvider::new_with_dir(&dir);
+
+        provider.save((2022, 1), "some puzzle input\n".to_string());
+        let loaded = provider.load((2022, 1));
+
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(loaded, Some("some puzzle input\n".to_string()));
+    }
+
+    #[test]
+    fn load_returns_none_when_content_is_corrupted() {
+        let dir = env::temp_dir().join(format!("libaoc-cache-test-corrupt-{}", std::process::id()));
+        let mut provider = FileCacheProvider::new_with_dir(&dir);
+
+        provider.save((2022, 1), "some puzzle input\n".to_string());
+        let file = dir.join("libaoc/2022/1.txt");
+        fs::write(&file, "tampered puzzle input\n").unwrap();
+        let loaded = provider.load((2022, 1));
+
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(loaded, None);
+    }
 }
@@ -0,0 +1,129 @@
+//! Sans-I/O cache state and decision logic shared between the blocking `AocClient` and the async
+//! `AsyncAocClient`. Everything here only touches in-memory state; all network requests and
+//! persistent cache I/O stay in the client that owns a `ClientCore`, since that's the only thing
+//! that actually differs between the sync and async surfaces. Keeping the decisions themselves in
+//! one place means a fix or a new rule can't be applied to one client and forgotten in the other.
+
+use crate::example_parse::Example;
+use crate::submission::{SubmissionOutcome, SubmissionResult};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// The result of checking the submission cache for an answer.
+pub(crate) enum SubmitDecision {
+    /// The result is already known; return it without making a request.
+    Known(SubmissionResult),
+    /// Nothing is known about this answer yet; the caller must make a request.
+    NeedsRequest,
+}
+
+pub(crate) struct ClientCore {
+    pub mem_cache: HashMap<(i32, i32), String>,
+    pub day_page_cache: HashMap<(i32, i32), String>,
+    pub day_page_fetch_times: HashMap<(i32, i32), SystemTime>,
+    pub example_ttl: Option<Duration>,
+    pub submission_cache: HashMap<(i32, i32, i32), HashMap<String, SubmissionOutcome>>,
+    pub answer_cooldowns: HashMap<(i32, i32, i32), SystemTime>,
+    pub throttle_timestamp: SystemTime,
+}
+
+impl ClientCore {
+    pub fn new(throttle_timestamp: SystemTime) -> Self {
+        Self {
+            mem_cache: HashMap::new(),
+            day_page_cache: HashMap::new(),
+            day_page_fetch_times: HashMap::new(),
+            example_ttl: None,
+            submission_cache: HashMap::new(),
+            answer_cooldowns: HashMap::new(),
+            throttle_timestamp,
+        }
+    }
+
+    pub fn set_example_ttl(&mut self, ttl: Duration) {
+        self.example_ttl = Some(ttl);
+    }
+
+    /// Whether a cached day page is stale under the configured `example_ttl`: the example it
+    /// holds has no part 2 data yet, and it was fetched longer ago than the TTL allows.
+    ///
+    /// `fetched_at` is the day page's fetch time if known; the caller is responsible for loading
+    /// it from the persistent cache into `day_page_fetch_times` first; loading it is I/O and so
+    /// isn't this struct's job.
+    pub fn example_is_stale(
+        &self,
+        example: &Option<Example>,
+        fetched_at: Option<SystemTime>,
+    ) -> bool {
+        let Some(ttl) = self.example_ttl else {
+            return false;
+        };
+        if example.as_ref().is_some_and(|e| e.part2_data.is_some()) {
+            return false;
+        }
+
+        match fetched_at {
+            Some(fetched_at) => SystemTime::now()
+                .duration_since(fetched_at)
+                .is_ok_and(|elapsed| elapsed >= ttl),
+            None => true,
+        }
+    }
+
+    /// Check the submission cache for `(year, day, part)`/`answer` without making a request.
+    pub fn submit_decision(&self, key: (i32, i32, i32), answer: &str) -> SubmitDecision {
+        let Some(outcomes) = self.submission_cache.get(&key) else {
+            return SubmitDecision::NeedsRequest;
+        };
+
+        if outcomes.get(answer) == Some(&SubmissionOutcome::Correct) {
+            return SubmitDecision::Known(SubmissionResult::Correct);
+        }
+        if outcomes.values().any(|o| *o == SubmissionOutcome::Correct) {
+            return SubmitDecision::Known(SubmissionResult::AlreadyCompleted);
+        }
+        if let Some(outcome) = outcomes.get(answer) {
+            return SubmitDecision::Known(outcome.clone().into());
+        }
+
+        SubmitDecision::NeedsRequest
+    }
+
+    /// Record the outcome of a submission made over the network.
+    pub fn record_submission_result(
+        &mut self,
+        key: (i32, i32, i32),
+        answer: &str,
+        result: &SubmissionResult,
+    ) {
+        match result {
+            SubmissionResult::Correct => {
+                self.submission_cache
+                    .entry(key)
+                    .or_default()
+                    .insert(answer.to_string(), SubmissionOutcome::Correct);
+            }
+            SubmissionResult::Incorrect { too_high } => {
+                self.submission_cache.entry(key).or_default().insert(
+                    answer.to_string(),
+                    SubmissionOutcome::Incorrect {
+                        too_high: *too_high,
+                    },
+                );
+            }
+            SubmissionResult::TooRecent { wait } => {
+                self.answer_cooldowns
+                    .insert(key, SystemTime::now() + *wait);
+            }
+            SubmissionResult::AlreadyCompleted => {}
+        }
+    }
+
+    /// The remaining cooldown before a submission may be retried for `key`, if one is recorded
+    /// and hasn't already elapsed.
+    pub fn cooldown_remaining(&self, key: (i32, i32, i32)) -> Option<Duration> {
+        self.answer_cooldowns
+            .get(&key)
+            .and_then(|&until| until.duration_since(SystemTime::now()).ok())
+    }
+}
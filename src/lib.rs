@@ -9,12 +9,20 @@
 //! suitable for your use case, create your own implementation of the `PersistentCacheProvider`
 //! trait.
 
+#[cfg(feature = "async")]
+pub mod async_client;
 pub mod cache;
+mod client_core;
 pub mod example_parse;
+pub mod puzzle;
+mod shared;
+pub mod submission;
 
 use crate::cache::{FileCacheProvider, PersistentCacheProvider};
+use crate::client_core::{ClientCore, SubmitDecision};
 use crate::example_parse::Example;
-use std::collections::HashMap;
+use crate::puzzle::Puzzle;
+use crate::submission::SubmissionResult;
 use std::thread::sleep;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -23,9 +31,7 @@ static MIN_TIME_BETWEEN_REQUESTS: Duration = Duration::from_secs(180);
 pub struct AocClient<C: PersistentCacheProvider> {
     session: String,
     client: reqwest::blocking::Client,
-    throttle_timestamp: SystemTime,
-    mem_cache: HashMap<(i32, i32), String>,
-    example_cache: HashMap<(i32, i32, i32), String>,
+    core: ClientCore,
     persistent_cache: C,
 }
 
@@ -47,38 +53,32 @@ impl AocClient<FileCacheProvider> {
 
 impl<C: PersistentCacheProvider> AocClient<C> {
     fn make_client() -> reqwest::blocking::Client {
-        let user_agent = format!(
-            "libaoc/{0} (automated; +https://github.com/arthomnix/libaoc; +{3}-{2}@{1}.dev) reqwest/0.12",
-            env!("CARGO_PKG_VERSION"),
-            "arthomnix", "contact", "libaoc",
-        );
-
         reqwest::blocking::Client::builder()
-            .user_agent(user_agent)
+            .user_agent(shared::user_agent())
             .build()
             .unwrap()
     }
 
     fn throttle(&mut self) -> bool {
-        let throttle_duration = SystemTime::now().duration_since(self.throttle_timestamp);
-        if throttle_duration
-            .as_ref()
-            .is_ok_and(|d| *d < MIN_TIME_BETWEEN_REQUESTS)
-        {
-            let sleep_duration = MIN_TIME_BETWEEN_REQUESTS - throttle_duration.unwrap();
-            eprintln!(
-                "libaoc: request throttled - sleeping for {}s",
-                sleep_duration.as_secs_f64()
-            );
-            sleep(sleep_duration);
-            self.throttle_timestamp = SystemTime::now();
-            true
-        } else if throttle_duration.is_err() {
-            eprintln!("libaoc: warning: received SystemTimeError while processing throttle, sleeping for 1 second and retrying...");
-            sleep(Duration::from_secs(1));
-            false
-        } else {
-            true
+        match shared::throttle_remaining(self.core.throttle_timestamp, MIN_TIME_BETWEEN_REQUESTS) {
+            Ok(Some(sleep_duration)) => {
+                eprintln!(
+                    "libaoc: request throttled - sleeping for {}s",
+                    sleep_duration.as_secs_f64()
+                );
+                sleep(sleep_duration);
+                self.core.throttle_timestamp = SystemTime::now();
+                true
+            }
+            Ok(None) => {
+                self.core.throttle_timestamp = SystemTime::now();
+                true
+            }
+            Err(()) => {
+                eprintln!("libaoc: warning: received SystemTimeError while processing throttle, sleeping for 1 second and retrying...");
+                sleep(Duration::from_secs(1));
+                false
+            }
         }
     }
 
@@ -92,12 +92,20 @@ impl<C: PersistentCacheProvider> AocClient<C> {
             session,
             persistent_cache: cache_provider,
             client: Self::make_client(),
-            throttle_timestamp,
-            mem_cache: HashMap::new(),
-            example_cache: HashMap::new(),
+            core: ClientCore::new(throttle_timestamp),
         }
     }
 
+    /// Set a freshness policy for cached examples: once an example with no part 2 data is older
+    /// than `ttl`, `get_example` will re-fetch it instead of serving the stale cache entry.
+    ///
+    /// This exists because the part 2 example (and answer) only appear in the HTML once the
+    /// account has completed part 1, so a cache populated before that point would otherwise serve
+    /// a part-1-only example forever.
+    pub fn set_example_ttl(&mut self, ttl: Duration) {
+        self.core.set_example_ttl(ttl);
+    }
+
     /// Get the input text for the Advent of Code puzzle for the given day and year, bypassing the cache.
     /// Only use this if you believe the cached input is corrupted.
     pub fn get_input_without_cache(&mut self, year: i32, day: i32) -> reqwest::Result<String> {
@@ -107,13 +115,13 @@ impl<C: PersistentCacheProvider> AocClient<C> {
 
         let text = self
             .client
-            .get(format!("https://adventofcode.com/{year}/day/{day}/input"))
-            .header("Cookie", format!("session={}", self.session))
+            .get(shared::input_url(year, day))
+            .header("Cookie", shared::cookie_header(&self.session))
             .send()
             .and_then(|r| r.text());
 
         if let Ok(text) = &text {
-            self.mem_cache.insert((year, day), text.clone());
+            self.core.mem_cache.insert((year, day), text.clone());
         }
 
         text
@@ -126,7 +134,8 @@ impl<C: PersistentCacheProvider> AocClient<C> {
         year: i32,
         day: i32,
     ) -> reqwest::Result<String> {
-        self.mem_cache
+        self.core
+            .mem_cache
             .get(&(year, day))
             .map(|s| Ok(s.clone()))
             .unwrap_or_else(|| self.get_input_without_cache(year, day))
@@ -134,42 +143,52 @@ impl<C: PersistentCacheProvider> AocClient<C> {
 
     /// Get the input text for the Advent of Code puzzle for the given day and year.
     pub fn get_input(&mut self, year: i32, day: i32) -> reqwest::Result<String> {
-        self.mem_cache
+        self.core
+            .mem_cache
             .get(&(year, day))
             .map(|s| Ok(s.clone()))
             .or_else(|| {
                 self.persistent_cache.load((year, day)).map(|o| {
-                    self.mem_cache.insert((year, day), o.clone());
+                    self.core.mem_cache.insert((year, day), o.clone());
                     Ok(o)
                 })
             })
             .unwrap_or_else(|| self.get_input_without_cache(year, day))
     }
 
-    /// Get the example input and (possibly unreliable) answer(s) for the given day and year, bypassing the cache.
-    /// Only use this if you believe the cache is corrupted, or you have completed part 1 and want to get the example answer for part 2 (which is hidden before part 1 is complete)>.
-    pub fn get_example_without_cache(
-        &mut self,
-        year: i32,
-        day: i32,
-        part: i32,
-    ) -> reqwest::Result<Option<Example>> {
+    /// Fetch the day page HTML directly from Advent of Code, bypassing the cache, and record it
+    /// in the day page cache. `get_example` and `get_puzzle` both parse this same page, so sharing
+    /// one fetch/cache of it means asking for both doesn't cost two requests (and two trips
+    /// through the throttle) for identical content.
+    fn fetch_day_page(&mut self, year: i32, day: i32) -> reqwest::Result<String> {
         if !self.throttle() {
-            return self.get_example_without_cache(year, day, part);
+            return self.fetch_day_page(year, day);
         }
 
         let html = self
             .client
-            .get(format!("https://adventofcode.com/{year}/day/{day}"))
-            .header("Cookie", format!("session={}", self.session))
+            .get(shared::day_url(year, day))
+            .header("Cookie", shared::cookie_header(&self.session))
             .send()
-            .and_then(|r| r.text());
+            .and_then(|r| r.text())?;
 
-        if let Ok(html) = &html {
-            self.example_cache.insert((year, day, part), html.clone());
-        }
+        self.core.day_page_cache.insert((year, day), html.clone());
+        self.core
+            .day_page_fetch_times
+            .insert((year, day), SystemTime::now());
+
+        Ok(html)
+    }
 
-        html.map(|html| Example::parse_example(html))
+    /// Get the example input and (possibly unreliable) answer(s) for the given day and year, bypassing the cache.
+    /// Only use this if you believe the cache is corrupted, or you have completed part 1 and want to get the example answer for part 2 (which is hidden before part 1 is complete)>.
+    pub fn get_example_without_cache(
+        &mut self,
+        year: i32,
+        day: i32,
+        _part: i32,
+    ) -> reqwest::Result<Option<Example>> {
+        self.fetch_day_page(year, day).map(Example::parse_example)
     }
 
     /// Get the example input and (possibly unreliable) answer(s) for the given day and year, bypassing the persistent cache but using the in-memory cache.
@@ -180,44 +199,158 @@ impl<C: PersistentCacheProvider> AocClient<C> {
         day: i32,
         part: i32,
     ) -> reqwest::Result<Option<Example>> {
-        self.example_cache
-            .get(&(year, day, part))
+        self.core
+            .day_page_cache
+            .get(&(year, day))
             .map(|s| Ok(Example::parse_example(s.clone())))
             .unwrap_or_else(|| self.get_example_without_cache(year, day, part))
     }
 
+    /// Whether the cached day page for `key` is stale under the configured `example_ttl`: the
+    /// example it holds has no part 2 data yet, and it was fetched longer ago than the TTL allows.
+    fn example_is_stale(&mut self, key: (i32, i32), example: &Option<Example>) -> bool {
+        if !self.core.day_page_fetch_times.contains_key(&key) {
+            if let Some(fetched_at) = self.persistent_cache.load_day_page_fetch_time(key) {
+                self.core.day_page_fetch_times.insert(key, fetched_at);
+            }
+        }
+
+        self.core
+            .example_is_stale(example, self.core.day_page_fetch_times.get(&key).copied())
+    }
+
     /// Get the example input and (possibly unreliable) answer(s) for the given day and year.
     ///
-    /// The `part` parameter is only used to cache the data for part 1 and part 2 separately (since
-    /// the answer for part 2 will only be available once your account has completed part 1). All
-    /// example data present in the HTML is returned regardless of the value of the parameter.
+    /// The `part` parameter is not used: the day page is cached once per `(year, day)`, and all
+    /// example data present in the HTML is returned regardless of the value of the parameter (the
+    /// answer for part 2 will only be present once your account has completed part 1).
+    ///
+    /// If an example TTL has been set with `set_example_ttl`, a cached example with no part 2 data
+    /// will be re-fetched once it is older than the TTL, instead of being served indefinitely.
     pub fn get_example(
         &mut self,
         year: i32,
         day: i32,
         part: i32,
     ) -> reqwest::Result<Option<Example>> {
-        self.example_cache
-            .get(&(year, day, part))
-            .map(|s| Ok(Example::parse_example(s.clone())))
+        let key = (year, day);
+        let cached_html = self
+            .core
+            .day_page_cache
+            .get(&key)
+            .cloned()
+            .or_else(|| self.persistent_cache.load_day_page(key));
+
+        if let Some(html) = cached_html {
+            self.core.day_page_cache.insert(key, html.clone());
+            let example = Example::parse_example(html);
+            if !self.example_is_stale(key, &example) {
+                return Ok(example);
+            }
+        }
+
+        self.get_example_without_cache(year, day, part)
+    }
+
+    /// Get the puzzle description (titles and prose, converted to Markdown) for the given day and
+    /// year, bypassing the cache.
+    pub fn get_puzzle_without_cache(
+        &mut self,
+        year: i32,
+        day: i32,
+    ) -> reqwest::Result<Option<Puzzle>> {
+        self.fetch_day_page(year, day).map(Puzzle::parse)
+    }
+
+    /// Get the puzzle description (titles and prose, converted to Markdown) for the given day and
+    /// year. Part 2's title/text will be `None` until your account has completed part 1.
+    pub fn get_puzzle(&mut self, year: i32, day: i32) -> reqwest::Result<Option<Puzzle>> {
+        let key = (year, day);
+        self.core
+            .day_page_cache
+            .get(&key)
+            .cloned()
+            .map(|html| Ok(Puzzle::parse(html)))
             .or_else(|| {
-                self.persistent_cache
-                    .load_example((year, day, part))
-                    .map(|o| {
-                        self.example_cache.insert((year, day, part), o.clone());
-                        Ok(Example::parse_example(o))
-                    })
+                self.persistent_cache.load_day_page(key).map(|html| {
+                    self.core.day_page_cache.insert(key, html.clone());
+                    Ok(Puzzle::parse(html))
+                })
             })
-            .unwrap_or_else(|| self.get_example_without_cache(year, day, part))
+            .unwrap_or_else(|| self.get_puzzle_without_cache(year, day))
+    }
+
+    /// Ensure the in-memory submission cache and cooldown for `key` reflect anything already
+    /// known to the persistent cache.
+    fn load_submission_state(&mut self, key: (i32, i32, i32)) {
+        if !self.core.submission_cache.contains_key(&key) {
+            let submissions = self.persistent_cache.load_submissions(key);
+            self.core.submission_cache.insert(key, submissions);
+        }
+        if !self.core.answer_cooldowns.contains_key(&key) {
+            if let Some(until) = self.persistent_cache.load_answer_cooldown(key) {
+                self.core.answer_cooldowns.insert(key, until);
+            }
+        }
+    }
+
+    /// Submit an answer for the given year, day and part.
+    ///
+    /// This is throttled using the same logic as input/example fetching, in accordance with the
+    /// Advent of Code automation guidelines. Answers already known to be correct or incorrect are
+    /// served from the cache without making a request; a submission that is too recent records a
+    /// cooldown which future calls will sleep through instead of wasting a request.
+    pub fn submit(
+        &mut self,
+        year: i32,
+        day: i32,
+        part: i32,
+        answer: &str,
+    ) -> reqwest::Result<SubmissionResult> {
+        let key = (year, day, part);
+        self.load_submission_state(key);
+
+        if let SubmitDecision::Known(result) = self.core.submit_decision(key, answer) {
+            return Ok(result);
+        }
+
+        if let Some(remaining) = self.core.cooldown_remaining(key) {
+            eprintln!(
+                "libaoc: submission throttled - sleeping for {}s",
+                remaining.as_secs_f64()
+            );
+            sleep(remaining);
+        }
+
+        if !self.throttle() {
+            return self.submit(year, day, part, answer);
+        }
+
+        let text = self
+            .client
+            .post(shared::submit_url(year, day))
+            .header("Cookie", shared::cookie_header(&self.session))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(shared::submit_body(part, answer))
+            .send()
+            .and_then(|r| r.text());
+
+        let result = text.map(SubmissionResult::parse)?;
+        self.core.record_submission_result(key, answer, &result);
+
+        Ok(result)
     }
 }
 
 impl<C: PersistentCacheProvider> Drop for AocClient<C> {
     fn drop(&mut self) {
         self.persistent_cache.save_all(
-            &self.mem_cache,
-            &self.example_cache,
-            self.throttle_timestamp,
+            &self.core.mem_cache,
+            &self.core.day_page_cache,
+            &self.core.day_page_fetch_times,
+            &self.core.submission_cache,
+            &self.core.answer_cooldowns,
+            self.core.throttle_timestamp,
         );
     }
 }
@@ -0,0 +1,165 @@
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// The prose of an Advent of Code puzzle, converted from HTML to plain text/Markdown.
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    pub part1_title: String,
+    pub part1_text: String,
+    pub part2_title: Option<String>,
+    pub part2_text: Option<String>,
+}
+
+impl Puzzle {
+    /// Parse the day page HTML (the same page `get_example` fetches) into a `Puzzle`.
+    pub fn parse(html: String) -> Option<Self> {
+        let document = Html::parse_document(&html);
+        let selector = Selector::parse("article.day-desc").ok()?;
+        let mut articles = document.select(&selector);
+
+        let part1 = articles.next()?;
+        let part2 = articles.next();
+
+        Some(Puzzle {
+            part1_title: Self::title(part1)?,
+            part1_text: Self::body(part1),
+            part2_title: part2.and_then(Self::title),
+            part2_text: part2.map(Self::body),
+        })
+    }
+
+    /// Extract the part title from the `<h2>`, e.g. `--- Day 1: Title ---` becomes `Title`.
+    fn title(article: ElementRef) -> Option<String> {
+        let selector = Selector::parse("h2").ok()?;
+        let h2 = article.select(&selector).next()?;
+        let text = h2.text().collect::<String>();
+        let cleaned = text.trim().trim_matches('-').trim();
+        Some(
+            cleaned
+                .split_once(':')
+                .map(|(_, title)| title.trim())
+                .unwrap_or(cleaned)
+                .to_string(),
+        )
+    }
+
+    /// Render the prose of a part as Markdown. Only the `<p>`, `<pre>` and `<ul>` children of the
+    /// article are rendered; the `<h2>` and any other elements are skipped.
+    fn body(article: ElementRef) -> String {
+        article
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|el| el.value().name() != "h2")
+            .filter_map(render_block)
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+fn render_block(element: ElementRef) -> Option<String> {
+    match element.value().name() {
+        "p" => Some(render_inline(element)),
+        "pre" => Some(format!("```\n{}\n```", element.text().collect::<String>())),
+        "ul" => {
+            let items = element
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|li| li.value().name() == "li")
+                .map(|li| format!("- {}", render_inline(li)))
+                .collect::<Vec<_>>();
+            Some(items.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Render an element's children as inline Markdown, converting `<em>`/`<strong>`/`<code>` to
+/// their Markdown equivalents and keeping link text for `<a>`.
+fn render_inline(element: ElementRef) -> String {
+    let mut out = String::new();
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(el) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    let inner = render_inline(child);
+                    match el.name() {
+                        "em" => out.push_str(&format!("*{inner}*")),
+                        "strong" | "b" => out.push_str(&format!("**{inner}**")),
+                        "code" => out.push_str(&format!("`{inner}`")),
+                        _ => out.push_str(&inner),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_extracts_title_and_body() {
+        let html = "<html><body><article class=\"day-desc\">\
+            <h2>--- Day 1: Report Repair ---</h2>\
+            <p>Before you leave, <em>the Elves</em> confiscate any items of a dangerous nature.</p>\
+            </article></body></html>"
+            .to_string();
+        let puzzle = Puzzle::parse(html).unwrap();
+        assert_eq!(puzzle.part1_title, "Report Repair");
+        assert_eq!(
+            puzzle.part1_text,
+            "Before you leave, *the Elves* confiscate any items of a dangerous nature."
+        );
+        assert_eq!(puzzle.part2_title, None);
+        assert_eq!(puzzle.part2_text, None);
+    }
+
+    #[test]
+    fn parse_splits_part1_and_part2() {
+        let html = "<html><body>\
+            <article class=\"day-desc\"><h2>--- Day 1: Report Repair ---</h2><p>Part one text.</p></article>\
+            <article class=\"day-desc\"><h2>--- Part Two ---</h2><p>Part two text.</p></article>\
+            </body></html>"
+            .to_string();
+        let puzzle = Puzzle::parse(html).unwrap();
+        assert_eq!(puzzle.part1_title, "Report Repair");
+        assert_eq!(puzzle.part1_text, "Part one text.");
+        assert_eq!(puzzle.part2_title, Some("Part Two".to_string()));
+        assert_eq!(puzzle.part2_text, Some("Part two text.".to_string()));
+    }
+
+    #[test]
+    fn parse_returns_none_without_day_desc_article() {
+        let html = "<html><body><p>No article here.</p></body></html>".to_string();
+        assert!(Puzzle::parse(html).is_none());
+    }
+
+    #[test]
+    fn render_inline_converts_em_strong_code() {
+        let html = "<html><body><article class=\"day-desc\">\
+            <h2>--- Day 1: Title ---</h2>\
+            <p><em>emphasised</em> and <strong>bold</strong> and <code>code</code></p>\
+            </article></body></html>"
+            .to_string();
+        let puzzle = Puzzle::parse(html).unwrap();
+        assert_eq!(puzzle.part1_text, "*emphasised* and **bold** and `code`");
+    }
+
+    #[test]
+    fn render_block_handles_pre_and_ul() {
+        let html = "<html><body><article class=\"day-desc\">\
+            <h2>--- Day 1: Title ---</h2>\
+            <pre>line one\nline two</pre>\
+            <ul><li>first</li><li>second</li></ul>\
+            </article></body></html>"
+            .to_string();
+        let puzzle = Puzzle::parse(html).unwrap();
+        assert_eq!(
+            puzzle.part1_text,
+            "```\nline one\nline two\n```\n\n- first\n- second"
+        );
+    }
+}
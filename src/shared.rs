@@ -0,0 +1,45 @@
+//! Request-building and throttle logic shared between `AocClient` and `AsyncAocClient`, so the
+//! two stay in sync as the public surface grows.
+
+use std::time::{Duration, SystemTime};
+
+pub(crate) fn input_url(year: i32, day: i32) -> String {
+    format!("https://adventofcode.com/{year}/day/{day}/input")
+}
+
+pub(crate) fn day_url(year: i32, day: i32) -> String {
+    format!("https://adventofcode.com/{year}/day/{day}")
+}
+
+pub(crate) fn submit_url(year: i32, day: i32) -> String {
+    format!("https://adventofcode.com/{year}/day/{day}/answer")
+}
+
+pub(crate) fn cookie_header(session: &str) -> String {
+    format!("session={session}")
+}
+
+pub(crate) fn submit_body(part: i32, answer: &str) -> String {
+    format!("level={part}&answer={answer}")
+}
+
+pub(crate) fn user_agent() -> String {
+    format!(
+        "libaoc/{0} (automated; +https://github.com/arthomnix/libaoc; +{3}-{2}@{1}.dev) reqwest/0.12",
+        env!("CARGO_PKG_VERSION"),
+        "arthomnix", "contact", "libaoc",
+    )
+}
+
+/// Returns `Some(remaining)` if a request made at `last_request` would violate `min_gap`, or
+/// `None` if enough time has already elapsed.
+pub(crate) fn throttle_remaining(
+    last_request: SystemTime,
+    min_gap: Duration,
+) -> Result<Option<Duration>, ()> {
+    match SystemTime::now().duration_since(last_request) {
+        Ok(elapsed) if elapsed < min_gap => Ok(Some(min_gap - elapsed)),
+        Ok(_) => Ok(None),
+        Err(_) => Err(()),
+    }
+}
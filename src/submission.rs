@@ -0,0 +1,192 @@
+use scraper::{Html, Selector};
+use std::time::Duration;
+
+/// The cacheable outcome of a submission - unlike `SubmissionResult`, this only covers the
+/// outcomes that are safe to remember and replay without re-submitting to Advent of Code.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SubmissionOutcome {
+    Correct,
+    Incorrect { too_high: Option<bool> },
+}
+
+impl SubmissionOutcome {
+    /// Serialise to the single-line format used by `FileCacheProvider`.
+    pub fn to_cache_string(&self) -> String {
+        match self {
+            SubmissionOutcome::Correct => "correct".to_string(),
+            SubmissionOutcome::Incorrect { too_high: None } => "incorrect".to_string(),
+            SubmissionOutcome::Incorrect {
+                too_high: Some(true),
+            } => "incorrect_high".to_string(),
+            SubmissionOutcome::Incorrect {
+                too_high: Some(false),
+            } => "incorrect_low".to_string(),
+        }
+    }
+
+    /// Parse the single-line format used by `FileCacheProvider`.
+    pub fn from_cache_string(s: &str) -> Option<Self> {
+        match s {
+            "correct" => Some(SubmissionOutcome::Correct),
+            "incorrect" => Some(SubmissionOutcome::Incorrect { too_high: None }),
+            "incorrect_high" => Some(SubmissionOutcome::Incorrect {
+                too_high: Some(true),
+            }),
+            "incorrect_low" => Some(SubmissionOutcome::Incorrect {
+                too_high: Some(false),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<SubmissionOutcome> for SubmissionResult {
+    fn from(outcome: SubmissionOutcome) -> Self {
+        match outcome {
+            SubmissionOutcome::Correct => SubmissionResult::Correct,
+            SubmissionOutcome::Incorrect { too_high } => SubmissionResult::Incorrect { too_high },
+        }
+    }
+}
+
+/// The result of submitting an answer to an Advent of Code puzzle.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SubmissionResult {
+    /// The submitted answer was correct.
+    Correct,
+    /// The submitted answer was incorrect. `too_high` is `Some(true)`/`Some(false)` if Advent of
+    /// Code hinted that the answer was too high/too low, or `None` if no such hint was given.
+    Incorrect { too_high: Option<bool> },
+    /// This part of the puzzle has already been completed, so the answer was not checked.
+    AlreadyCompleted,
+    /// Too many submissions have been made recently; wait for the given duration before
+    /// submitting again.
+    TooRecent { wait: Duration },
+}
+
+impl SubmissionResult {
+    /// Parse the `<article>` returned by the Advent of Code answer-submission endpoint.
+    pub fn parse(html: String) -> Self {
+        let document = Html::parse_document(&html);
+        let selector = Selector::parse("article").unwrap();
+        let text = document
+            .select(&selector)
+            .next()
+            .map(|el| el.text().collect::<String>())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if text.contains("that's the right answer") {
+            SubmissionResult::Correct
+        } else if text.contains("you don't seem to be solving the right level") {
+            SubmissionResult::AlreadyCompleted
+        } else if text.contains("you gave an answer too recently") {
+            SubmissionResult::TooRecent {
+                wait: Self::parse_wait(&text).unwrap_or(Duration::from_secs(60)),
+            }
+        } else {
+            let too_high = if text.contains("too high") {
+                Some(true)
+            } else if text.contains("too low") {
+                Some(false)
+            } else {
+                None
+            };
+            SubmissionResult::Incorrect { too_high }
+        }
+    }
+
+    /// Parse a "you have 5m 23s left to wait" message into a `Duration`.
+    fn parse_wait(text: &str) -> Option<Duration> {
+        let left_to_wait = text.find("left to wait")?;
+        let have = text[..left_to_wait].rfind("have ")? + "have ".len();
+        let time_str = text[have..left_to_wait].trim();
+
+        let mut seconds = 0u64;
+        for part in time_str.split_whitespace() {
+            if let Some(m) = part.strip_suffix('m') {
+                seconds += m.parse::<u64>().ok()? * 60;
+            } else if let Some(s) = part.strip_suffix('s') {
+                seconds += s.parse::<u64>().ok()?;
+            }
+        }
+
+        Some(Duration::from_secs(seconds))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_correct() {
+        let html = "<article><p>That's the right answer! You are one gold star closer...</p></article>".to_string();
+        assert_eq!(SubmissionResult::parse(html), SubmissionResult::Correct);
+    }
+
+    #[test]
+    fn parse_incorrect_too_high() {
+        let html = "<article><p>That's not the right answer; your answer is too high. If you're stuck...</p></article>".to_string();
+        assert_eq!(
+            SubmissionResult::parse(html),
+            SubmissionResult::Incorrect {
+                too_high: Some(true)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_incorrect_too_low() {
+        let html = "<article><p>That's not the right answer; your answer is too low. If you're stuck...</p></article>".to_string();
+        assert_eq!(
+            SubmissionResult::parse(html),
+            SubmissionResult::Incorrect {
+                too_high: Some(false)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_incorrect_no_hint() {
+        let html =
+            "<article><p>That's not the right answer. If you're stuck...</p></article>"
+                .to_string();
+        assert_eq!(
+            SubmissionResult::parse(html),
+            SubmissionResult::Incorrect { too_high: None }
+        );
+    }
+
+    #[test]
+    fn parse_already_completed() {
+        let html = "<article><p>You don't seem to be solving the right level. Did you already complete it?</p></article>".to_string();
+        assert_eq!(
+            SubmissionResult::parse(html),
+            SubmissionResult::AlreadyCompleted
+        );
+    }
+
+    #[test]
+    fn parse_too_recent_with_wait_time() {
+        let html = "<article><p>You gave an answer too recently; you have 5m 23s left to wait.</p></article>".to_string();
+        assert_eq!(
+            SubmissionResult::parse(html),
+            SubmissionResult::TooRecent {
+                wait: Duration::from_secs(5 * 60 + 23)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_too_recent_without_wait_time_falls_back_to_default() {
+        let html =
+            "<article><p>You gave an answer too recently.</p></article>".to_string();
+        assert_eq!(
+            SubmissionResult::parse(html),
+            SubmissionResult::TooRecent {
+                wait: Duration::from_secs(60)
+            }
+        );
+    }
+}